@@ -0,0 +1,93 @@
+use std::fmt;
+
+/// Errors raised while parsing nomenclature values (ligand motifs, allele
+/// designations, ...) from their string representation.
+#[derive(Debug)]
+pub enum NomenclatureError {
+    UnknownLigandMotif(String),
+}
+
+impl fmt::Display for NomenclatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NomenclatureError::UnknownLigandMotif(motif) => {
+                write!(f, "unknown ligand motif '{}'", motif)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NomenclatureError {}
+
+/// Errors raised while retrieving or parsing the IPD KIR ligand table, and
+/// while persisting/loading a `KirLigandMap` cache.
+#[derive(Debug)]
+pub enum HtmlParseError {
+    CouldNotReadResponse(attohttpc::Error),
+    CouldNotReadClassI(String),
+    IncorrectNumberOfColumns(usize, String),
+    CouldNotConnectAsync(reqwest::Error),
+    CouldNotReadResponseAsync(reqwest::Error),
+    CouldNotWriteCache(std::io::Error),
+    CouldNotReadCache(std::io::Error),
+    CouldNotSerializeCache(serde_json::Error),
+    CouldNotDeserializeCache(serde_json::Error),
+    StaleCacheSchema(u32, u32),
+    StaleCacheAge(u64, u64),
+}
+
+impl fmt::Display for HtmlParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HtmlParseError::CouldNotReadResponse(err) => {
+                write!(f, "could not read IPD response: {}", err)
+            }
+            HtmlParseError::CouldNotReadClassI(value) => {
+                write!(f, "could not parse '{}' as a ClassI allele or ligand motif", value)
+            }
+            HtmlParseError::IncorrectNumberOfColumns(count, row) => {
+                write!(f, "expected 3 columns, found {} in row '{}'", count, row)
+            }
+            HtmlParseError::CouldNotConnectAsync(err) => {
+                write!(f, "could not connect to IPD: {}", err)
+            }
+            HtmlParseError::CouldNotReadResponseAsync(err) => {
+                write!(f, "could not read IPD response: {}", err)
+            }
+            HtmlParseError::CouldNotWriteCache(err) => {
+                write!(f, "could not write cache file: {}", err)
+            }
+            HtmlParseError::CouldNotReadCache(err) => {
+                write!(f, "could not read cache file: {}", err)
+            }
+            HtmlParseError::CouldNotSerializeCache(err) => {
+                write!(f, "could not serialize cache: {}", err)
+            }
+            HtmlParseError::CouldNotDeserializeCache(err) => {
+                write!(f, "could not deserialize cache: {}", err)
+            }
+            HtmlParseError::StaleCacheSchema(found, expected) => {
+                write!(
+                    f,
+                    "cache schema version {} does not match expected version {}",
+                    found, expected
+                )
+            }
+            HtmlParseError::StaleCacheAge(age_secs, max_age_secs) => {
+                write!(
+                    f,
+                    "cache is {}s old, exceeding the maximum age of {}s",
+                    age_secs, max_age_secs
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HtmlParseError {}
+
+impl From<attohttpc::Error> for HtmlParseError {
+    fn from(err: attohttpc::Error) -> Self {
+        HtmlParseError::CouldNotReadResponse(err)
+    }
+}
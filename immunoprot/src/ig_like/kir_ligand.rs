@@ -1,18 +1,36 @@
 use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::error::{HtmlParseError, NomenclatureError};
 use crate::mhc::hla::ClassI;
 use log::info;
 use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
 
 type Result<T> = std::result::Result<T, NomenclatureError>;
 
 pub const IPD_KIR_URL: &str = "https://www.ebi.ac.uk/cgi-bin/ipd/kir/retrieve_ligands.cgi?";
 pub const GENE_LOCI: [&str; 3] = ["A", "B", "C"];
 pub const SKIP_ROWS: usize = 1;
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Upper bound on the retry backoff's exponent, so a large `max_retries`
+/// can't overflow the `2^attempt` shift or the subsequent duration multiply.
+const MAX_BACKOFF_EXPONENT: u32 = 10;
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+/// Bumped whenever the on-disk cache layout changes; `KirLigandMap::load`
+/// refuses to read a cache written by a different schema version.
+pub const CACHE_SCHEMA_VERSION: u32 = 1;
+
+/// A cache older than this is considered stale; `KirLigandMap::load` rejects
+/// it so the caller falls back to a live fetch instead of serving data that
+/// the IPD source may have long since updated.
+pub const CACHE_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
 pub enum LigandMotif {
     A11,
     A3,
@@ -61,7 +79,7 @@ impl std::fmt::Display for LigandMotif {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
 pub enum AlleleFreq {
     Rare,
     Common,
@@ -82,6 +100,18 @@ where
     }
 }
 
+impl std::fmt::Display for AlleleFreq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use AlleleFreq::*;
+        let freq = match self {
+            Rare => "Rare",
+            Common => "Common",
+            Unknown => "Unknown",
+        };
+        write!(f, "{}", freq)
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub struct KirLigandInfo(ClassI, LigandMotif, AlleleFreq);
 
@@ -103,6 +133,82 @@ impl KirLigandInfo {
     }
 }
 
+// `ClassI` has no serde impl of its own (see `src/io/ser.rs`, which
+// round-trips it through `to_string`/`FromStr` by hand), so `KirLigandInfo`
+// is serialized the same way: as its three fields' string forms rather than
+// via `#[derive(Serialize, Deserialize)]`.
+impl Serialize for KirLigandInfo {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (self.0.to_string(), self.1.to_string(), self.2.to_string()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for KirLigandInfo {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (allele, motif, freq) = <(String, String, String)>::deserialize(deserializer)?;
+
+        let allele = allele
+            .parse::<ClassI>()
+            .map_err(|_| serde::de::Error::custom(format!("invalid allele '{}'", allele)))?;
+        let motif = motif
+            .parse::<LigandMotif>()
+            .map_err(|_| serde::de::Error::custom(format!("invalid ligand motif '{}'", motif)))?;
+        let freq = AlleleFreq::from(freq);
+
+        Ok(KirLigandInfo::new(allele, motif, freq))
+    }
+}
+
+/// Lookup semantics for `KirLigandMap::get_allele_info_by_mode`.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum AlleleLookup {
+    Exact,
+    Prefix,
+}
+
+/// Splits an allele's string representation on `:` into its HLA fields
+/// (allele group, protein, then any synonymous/intron fields), used by the
+/// `AlleleLookup::Prefix` comparison.
+fn allele_fields(allele: &ClassI) -> Vec<String> {
+    allele.to_string().split(':').map(str::to_string).collect()
+}
+
+/// Schema-versioned header written ahead of the cached entries, mirroring
+/// the way rustc stamps its crate metadata with a format version so a
+/// mismatched or stale cache can be detected. `load` parses the whole file,
+/// header and entries, before it checks the header.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheHeader {
+    schema_version: u32,
+    fetched_at: u64,
+}
+
+impl CacheHeader {
+    fn current() -> Self {
+        let fetched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+
+        Self {
+            schema_version: CACHE_SCHEMA_VERSION,
+            fetched_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    header: CacheHeader,
+    entries: Vec<KirLigandInfo>,
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct KirLigandMap {
     pub alleles: HashSet<ClassI>,
@@ -112,13 +218,20 @@ pub struct KirLigandMap {
 impl KirLigandMap {
 
     fn new(loci: &[&str]) -> std::result::Result<Self, HtmlParseError> {
+        Self::new_with_config(loci, &IpdRequestConfig::default())
+    }
+
+    fn new_with_config(
+        loci: &[&str],
+        config: &IpdRequestConfig,
+    ) -> std::result::Result<Self, HtmlParseError> {
         let mut alleles = HashSet::<ClassI>::new();
         let mut cache = HashMap::<ClassI, KirLigandInfo>::new();
 
-        let _: std::result::Result<Vec<_>, HtmlParseError> = loci
+        let fetches: std::result::Result<Vec<_>, HtmlParseError> = loci
             .iter()
             .map(|locus| {
-                let raw_html = get_ipd_html(locus)?;
+                let raw_html = get_ipd_html_with_config(locus, config)?;
                 let allele_infos = read_table(&raw_html, SKIP_ROWS)?;
 
                 for allele_info in allele_infos {
@@ -129,34 +242,318 @@ impl KirLigandMap {
                 Ok(())
             })
             .collect();
+        fetches?;
+
+        Ok(Self { alleles, cache })
+    }
+
+    /// Async counterpart of `new` that issues all per-locus requests
+    /// concurrently instead of one round-trip at a time, joining the
+    /// results once every locus has responded.
+    pub async fn new_async(loci: &[&str]) -> std::result::Result<Self, HtmlParseError> {
+        let fetches = loci.iter().map(|locus| async move {
+            let raw_html = get_ipd_html_async(locus).await?;
+            read_table(&raw_html, SKIP_ROWS)
+        });
+
+        let per_locus_infos = futures::future::try_join_all(fetches).await?;
+
+        let mut alleles = HashSet::<ClassI>::new();
+        let mut cache = HashMap::<ClassI, KirLigandInfo>::new();
+
+        for allele_infos in per_locus_infos {
+            for allele_info in allele_infos {
+                alleles.insert(allele_info.0.clone());
+                cache.insert(allele_info.0.clone(), allele_info);
+            }
+        }
 
         Ok(Self { alleles, cache })
     }
 
     fn  get_allele_info(&self, allele: &ClassI) -> Vec<&KirLigandInfo> {
-        let mut kir_ligand_info = Vec::<&KirLigandInfo>::new();
+        self.get_allele_info_by_mode(allele, AlleleLookup::Exact)
+    }
 
-        if self.alleles.contains(allele) {
-            if let Some(allele_info) = self.cache.get(allele) {
-                kir_ligand_info.push(allele_info)
+    /// Resolution-aware lookup. With `AlleleLookup::Exact` this behaves like
+    /// `get_allele_info`; with `AlleleLookup::Prefix`, `allele` is treated as
+    /// a low-resolution query (e.g. `C*01:02`) and every cached entry whose
+    /// colon-delimited fields share that prefix is returned (e.g.
+    /// `C*01:02:01:01`, `C*01:02:01:02`). A query with more fields than a
+    /// cached allele never matches it, since there are no further fields on
+    /// the cached side left to compare.
+    pub fn get_allele_info_by_mode(&self, allele: &ClassI, mode: AlleleLookup) -> Vec<&KirLigandInfo> {
+        match mode {
+            AlleleLookup::Exact => {
+                let mut kir_ligand_info = Vec::<&KirLigandInfo>::new();
+
+                if self.alleles.contains(allele) {
+                    if let Some(allele_info) = self.cache.get(allele) {
+                        kir_ligand_info.push(allele_info)
+                    }
+                }
+                kir_ligand_info
             }
+            AlleleLookup::Prefix => {
+                let query_fields = allele_fields(allele);
+
+                self.cache
+                    .values()
+                    .filter(|allele_info| {
+                        let candidate_fields = allele_fields(allele_info.allele());
+                        query_fields.len() <= candidate_fields.len()
+                            && query_fields
+                                .iter()
+                                .zip(candidate_fields.iter())
+                                .all(|(query_field, candidate_field)| query_field == candidate_field)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Persists the full allele/cache set to `path` as JSON, stamped with a
+    /// schema version and the fetch timestamp so `load` can tell a stale or
+    /// incompatible cache apart from a fresh one.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), HtmlParseError> {
+        let cache_file = CacheFile {
+            header: CacheHeader::current(),
+            entries: self.cache.values().cloned().collect(),
+        };
+
+        let file = File::create(path).map_err(HtmlParseError::CouldNotWriteCache)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), &cache_file)
+            .map_err(HtmlParseError::CouldNotSerializeCache)
+    }
+
+    /// Loads a map previously written by `save`. Fails if the file is
+    /// unreadable, malformed, written by an incompatible schema version, or
+    /// older than `CACHE_MAX_AGE`, leaving it to the caller to fall back to
+    /// a live fetch.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::result::Result<Self, HtmlParseError> {
+        let file = File::open(path).map_err(HtmlParseError::CouldNotReadCache)?;
+        let cache_file: CacheFile = serde_json::from_reader(BufReader::new(file))
+            .map_err(HtmlParseError::CouldNotDeserializeCache)?;
+
+        if cache_file.header.schema_version != CACHE_SCHEMA_VERSION {
+            return Err(HtmlParseError::StaleCacheSchema(
+                cache_file.header.schema_version,
+                CACHE_SCHEMA_VERSION,
+            ));
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        let age_secs = now.saturating_sub(cache_file.header.fetched_at);
+
+        if age_secs > CACHE_MAX_AGE.as_secs() {
+            return Err(HtmlParseError::StaleCacheAge(
+                age_secs,
+                CACHE_MAX_AGE.as_secs(),
+            ));
+        }
+
+        let mut alleles = HashSet::<ClassI>::new();
+        let mut cache = HashMap::<ClassI, KirLigandInfo>::new();
+
+        for allele_info in cache_file.entries {
+            alleles.insert(allele_info.0.clone());
+            cache.insert(allele_info.0.clone(), allele_info);
+        }
+
+        Ok(Self { alleles, cache })
+    }
+
+    /// Loads `path` if it holds a fresh, schema-compatible cache; otherwise
+    /// falls back to a live fetch over `loci`. A map obtained by the live
+    /// fetch is returned as is; it is not written to `path`.
+    pub fn load_or_fetch<P: AsRef<Path>>(
+        path: P,
+        loci: &[&str],
+    ) -> std::result::Result<Self, HtmlParseError> {
+        match Self::load(path) {
+            Ok(map) => Ok(map),
+            Err(_) => Self::new(loci),
+        }
+    }
+
+    /// Builds a map directly from a TSV dump (e.g. the bundled
+    /// `resources/2019-12-29_lg.tsv`) instead of a live fetch, via
+    /// `read_tsv_table`.
+    pub fn from_tsv(tsv: &str) -> std::result::Result<Self, HtmlParseError> {
+        let mut alleles = HashSet::<ClassI>::new();
+        let mut cache = HashMap::<ClassI, KirLigandInfo>::new();
+
+        for allele_info in read_tsv_table(tsv, SKIP_ROWS)? {
+            alleles.insert(allele_info.0.clone());
+            cache.insert(allele_info.0.clone(), allele_info);
+        }
+
+        Ok(Self { alleles, cache })
+    }
+}
+
+/// Builds a `KirLigandMap` with a configurable endpoint, locus set, request
+/// timeout, retry count and an offline mode that loads from a cache file
+/// instead of the network, e.g.:
+///
+/// ```ignore
+/// let map = KirLigandMapBuilder::new()
+///     .endpoint("https://ipd-mirror.local/retrieve_ligands.cgi?")
+///     .loci(vec!["A", "B", "C"])
+///     .max_retries(3)
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct KirLigandMapBuilder {
+    loci: Vec<String>,
+    config: IpdRequestConfig,
+    offline_cache_path: Option<PathBuf>,
+}
+
+impl Default for KirLigandMapBuilder {
+    fn default() -> Self {
+        Self {
+            loci: GENE_LOCI.iter().map(|locus| locus.to_string()).collect(),
+            config: IpdRequestConfig::default(),
+            offline_cache_path: None,
+        }
+    }
+}
+
+impl KirLigandMapBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn endpoint<T: Into<String>>(mut self, endpoint: T) -> Self {
+        self.config.endpoint = endpoint.into();
+        self
+    }
+
+    pub fn loci<T: Into<String>>(mut self, loci: Vec<T>) -> Self {
+        self.loci = loci.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = timeout;
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.config.max_retries = max_retries;
+        self
+    }
+
+    /// Forces `build` to load `cache_path` instead of contacting the
+    /// network at all.
+    pub fn offline<P: Into<PathBuf>>(mut self, cache_path: P) -> Self {
+        self.offline_cache_path = Some(cache_path.into());
+        self
+    }
+
+    pub fn build(self) -> std::result::Result<KirLigandMap, HtmlParseError> {
+        if let Some(cache_path) = self.offline_cache_path {
+            return KirLigandMap::load(cache_path);
         }
-        kir_ligand_info
+
+        let loci: Vec<&str> = self.loci.iter().map(String::as_str).collect();
+        KirLigandMap::new_with_config(&loci, &self.config)
     }
 }
 
 /// Obtains raw HTL from the EBI website
 pub fn get_ipd_html<T>(gene_locus: T) -> std::result::Result<Html, HtmlParseError>
+where
+    T: AsRef<str> + std::fmt::Display,
+{
+    get_ipd_html_with_config(gene_locus, &IpdRequestConfig::default())
+}
+
+/// Configures the endpoint, request timeout and retry-with-backoff count
+/// used by a `get_ipd_html*` call. Built up by `KirLigandMapBuilder` so a
+/// flaky network or a local IPD mirror can be handled without touching
+/// caller code.
+#[derive(Debug, Clone)]
+pub struct IpdRequestConfig {
+    pub endpoint: String,
+    pub timeout: Duration,
+    pub max_retries: u32,
+}
+
+impl Default for IpdRequestConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: IPD_KIR_URL.to_string(),
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+            max_retries: 0,
+        }
+    }
+}
+
+/// Same as `get_ipd_html`, but honours `config`'s endpoint, timeout and
+/// bounded retry-with-backoff count instead of the hardcoded defaults.
+pub fn get_ipd_html_with_config<T>(
+    gene_locus: T,
+    config: &IpdRequestConfig,
+) -> std::result::Result<Html, HtmlParseError>
+where
+    T: AsRef<str> + std::fmt::Display,
+{
+    let url = format!("{}{}", config.endpoint, &gene_locus);
+    info!("Connecting to {}...", &url);
+
+    let mut attempt = 0;
+    loop {
+        let request = attohttpc::get(&url).timeout(config.timeout);
+
+        match request.send() {
+            Ok(response) => {
+                let text = response
+                    .text()
+                    .map_err(HtmlParseError::CouldNotReadResponse)?;
+
+                info!(
+                    "Obtained response, looking for allele table for locus '{}'",
+                    gene_locus
+                );
+
+                return Ok(Html::parse_document(&text));
+            }
+            Err(err) if attempt < config.max_retries => {
+                attempt += 1;
+                info!(
+                    "Request for locus '{}' failed ({}), retrying ({}/{})...",
+                    gene_locus, err, attempt, config.max_retries
+                );
+                // Exponent is capped so the shift and multiplication can't
+                // overflow even when `max_retries` is very large.
+                let exponent = (attempt - 1).min(MAX_BACKOFF_EXPONENT);
+                std::thread::sleep(Duration::from_millis(200).saturating_mul(1u32 << exponent));
+            }
+            Err(err) => return Err(HtmlParseError::from(err)),
+        }
+    }
+}
+
+/// Non-blocking counterpart of `get_ipd_html`, used by `KirLigandMap::new_async`
+/// so per-locus requests can be issued concurrently instead of serially.
+pub async fn get_ipd_html_async<T>(gene_locus: T) -> std::result::Result<Html, HtmlParseError>
 where
     T: AsRef<str> + std::fmt::Display,
 {
     let url = format!("{}{}", IPD_KIR_URL, &gene_locus);
     info!("Connecting to {}...", &url);
-    let request = attohttpc::get(&url);
-    let response = request.send()?;
+    let response = reqwest::get(&url)
+        .await
+        .map_err(HtmlParseError::CouldNotConnectAsync)?;
     let text = response
         .text()
-        .or_else(|err| Err(HtmlParseError::CouldNotReadResponse(err)))?;
+        .await
+        .map_err(HtmlParseError::CouldNotReadResponseAsync)?;
 
     info!(
         "Obtained response, looking for allele table for locus '{}'",
@@ -202,10 +599,49 @@ pub fn read_table(
     Ok(result)
 }
 
+/// Parses a tab-separated dump in the same 3-column layout as the fetched
+/// HTML table (allele, motif, frequency), e.g. the bundled
+/// `resources/2019-12-29_lg.tsv`, letting it be loaded as real data without
+/// touching the network.
+pub fn read_tsv_table(
+    tsv: &str,
+    skip_rows: usize,
+) -> std::result::Result<Vec<KirLigandInfo>, HtmlParseError> {
+    let mut result = Vec::<KirLigandInfo>::new();
+
+    for line in tsv.lines().skip(skip_rows) {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let columns: Vec<&str> = line.split('\t').collect();
+
+        if columns.len() == 3 {
+            let allele = columns[0]
+                .parse::<ClassI>()
+                .map_err(|_| HtmlParseError::CouldNotReadClassI(columns[0].to_string()))?;
+            let motif = columns[1]
+                .parse::<LigandMotif>()
+                .map_err(|_| HtmlParseError::CouldNotReadClassI(columns[1].to_string()))?;
+            let freq: AlleleFreq = columns[2].into();
+
+            result.push(KirLigandInfo::new(allele, motif, freq));
+        } else {
+            return Err(HtmlParseError::IncorrectNumberOfColumns(
+                columns.len(),
+                line.to_string(),
+            ));
+        }
+    }
+
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::ig_like::kir_ligand::{
-        get_ipd_html, read_table, AlleleFreq, KirLigandInfo, KirLigandMap, LigandMotif,
+        get_ipd_html, read_table, AlleleFreq, AlleleLookup, KirLigandInfo, KirLigandMap,
+        KirLigandMapBuilder, LigandMotif, GENE_LOCI,
     };
     use crate::mhc::hla::ClassI;
 
@@ -219,9 +655,10 @@ mod tests {
     fn test_ligand_info() {
         let lg_info = include_str!("../resources/2019-12-29_lg.tsv");
 
-        lg_info.lines().for_each(|l| {
-            dbg!(l);
-        });
+        let ligand_map = KirLigandMap::from_tsv(lg_info).unwrap();
+
+        assert!(!ligand_map.alleles.is_empty());
+        assert_eq!(ligand_map.alleles.len(), ligand_map.cache.len());
     }
 
     #[test]
@@ -250,4 +687,110 @@ mod tests {
 
         dbg!(ligand_map.alleles);
     }
+
+    #[tokio::test]
+    #[ignore] // hits the live IPD-KIR endpoint; run explicitly, not in CI
+    async fn test_create_ligand_map_async() {
+        let ligand_map = KirLigandMap::new_async(&GENE_LOCI).await.unwrap();
+
+        dbg!(ligand_map.alleles);
+    }
+
+    #[test]
+    fn test_save_and_load_cache_roundtrip() {
+        let mut path = std::env::temp_dir();
+        path.push("kir_ligand_map_roundtrip_test.json");
+
+        let mut alleles = std::collections::HashSet::new();
+        let mut cache = std::collections::HashMap::new();
+        let allele_info = KirLigandInfo::new(
+            "C*01:02:01:01".parse::<ClassI>().unwrap(),
+            LigandMotif::C1,
+            AlleleFreq::Common,
+        );
+        alleles.insert(allele_info.allele().clone());
+        cache.insert(allele_info.allele().clone(), allele_info);
+        let ligand_map = KirLigandMap { alleles, cache };
+
+        ligand_map.save(&path).unwrap();
+        let loaded = KirLigandMap::load(&path).unwrap();
+
+        assert_eq!(ligand_map, loaded);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_get_allele_info_prefix_match() {
+        let mut alleles = std::collections::HashSet::new();
+        let mut cache = std::collections::HashMap::new();
+
+        for allele in [
+            "C*01:02:01:01",
+            "C*01:02:01:02",
+            "C*01:03:01:01",
+            "C*02:02:02:01",
+        ] {
+            let allele_info = KirLigandInfo::new(
+                allele.parse::<ClassI>().unwrap(),
+                LigandMotif::C1,
+                AlleleFreq::Common,
+            );
+            alleles.insert(allele_info.allele().clone());
+            cache.insert(allele_info.allele().clone(), allele_info);
+        }
+        let ligand_map = KirLigandMap { alleles, cache };
+
+        let query = "C*01:02".parse::<ClassI>().unwrap();
+        let matches = ligand_map.get_allele_info_by_mode(&query, AlleleLookup::Prefix);
+
+        assert_eq!(matches.len(), 2);
+        assert!(matches
+            .iter()
+            .all(|m| m.allele().to_string().starts_with("C*01:02")));
+    }
+
+    #[test]
+    fn test_get_allele_info_prefix_no_match_when_query_has_more_fields() {
+        let mut alleles = std::collections::HashSet::new();
+        let mut cache = std::collections::HashMap::new();
+        let allele_info = KirLigandInfo::new(
+            "C*01:02".parse::<ClassI>().unwrap(),
+            LigandMotif::C1,
+            AlleleFreq::Common,
+        );
+        alleles.insert(allele_info.allele().clone());
+        cache.insert(allele_info.allele().clone(), allele_info);
+        let ligand_map = KirLigandMap { alleles, cache };
+
+        let query = "C*01:02:01:01".parse::<ClassI>().unwrap();
+        let matches = ligand_map.get_allele_info_by_mode(&query, AlleleLookup::Prefix);
+
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_builder_offline_loads_from_cache() {
+        let mut path = std::env::temp_dir();
+        path.push("kir_ligand_map_builder_offline_test.json");
+
+        let mut alleles = std::collections::HashSet::new();
+        let mut cache = std::collections::HashMap::new();
+        let allele_info = KirLigandInfo::new(
+            "C*01:02:01:01".parse::<ClassI>().unwrap(),
+            LigandMotif::C1,
+            AlleleFreq::Common,
+        );
+        alleles.insert(allele_info.allele().clone());
+        cache.insert(allele_info.allele().clone(), allele_info);
+        let ligand_map = KirLigandMap { alleles, cache };
+        ligand_map.save(&path).unwrap();
+
+        let loaded = KirLigandMapBuilder::new()
+            .offline(path.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(ligand_map, loaded);
+        std::fs::remove_file(&path).unwrap();
+    }
 }